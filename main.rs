@@ -1,4 +1,4 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 
 const NUM_CITIES: usize = 20;
 const NUM_PARTICLES: usize = 500;
@@ -9,6 +9,87 @@ const COGNITIVE_COMPONENT: f64 = 1.49445;
 const SOCIAL_COMPONENT: f64 = 1.49445;
 const MUTATION_RATE: f64 = 0.1;
 const PRUNE_PERCENTAGE: usize = 10;
+const TWO_OPT_INTERVAL: usize = 50;
+const NEAREST_NEIGHBOR_SEED_FRACTION: f64 = 0.1;
+const SA_START_TEMP: f64 = 1e6;
+const SA_END_TEMP: f64 = 1e2;
+const SA_CLOCK_CHECK_INTERVAL: u64 = 1000;
+const HELD_KARP_MAX_CITIES: usize = 16;
+const KMEANS_MAX_ITERATIONS: usize = 100;
+const RELAY_HOP_DISCOUNT: f64 = 0.5;
+
+/// Selects which optimizer `main` drives the search with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Strategy {
+    Pso,
+    SimulatedAnnealing,
+    TwoOptOnly,
+    Exact,
+}
+
+/// A seeded Xoshiro256++ PRNG, used in place of clock-based "randomness" so
+/// runs are both statistically sound and reproducible given the same seed.
+struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /// Seeds the four Xoshiro256++ state words from a single `u64` via
+    /// splitmix64, as recommended by the Xoshiro authors.
+    fn new(seed: u64) -> Self {
+        let mut z = seed;
+        let mut next_word = || {
+            z = z.wrapping_add(0x9e3779b97f4a7c15);
+            let mut result = z;
+            result = (result ^ (result >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            result = (result ^ (result >> 27)).wrapping_mul(0x94d049bb133111eb);
+            result ^ (result >> 31)
+        };
+        Rng {
+            state: [next_word(), next_word(), next_word(), next_word()],
+        }
+    }
+
+    fn rotl(x: u64, k: u32) -> u64 {
+        (x << k) | (x >> (64 - k))
+    }
+
+    /// Advances the generator and returns the next 64-bit output.
+    fn next(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = Self::rotl(s[0].wrapping_add(s[3]), 23).wrapping_add(s[0]);
+
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = Self::rotl(s[3], 45);
+
+        result
+    }
+
+    /// Returns a uniformly distributed integer in `[lo, hi)`, using rejection
+    /// sampling so the result is unbiased even when `hi - lo` doesn't divide
+    /// `u64::MAX + 1` evenly.
+    fn gen_range(&mut self, lo: i32, hi: i32) -> i32 {
+        assert!(hi > lo, "gen_range: hi must be greater than lo");
+        let span = (hi - lo) as u64;
+        let zone = u64::MAX - (u64::MAX % span);
+        loop {
+            let draw = self.next();
+            if draw < zone {
+                return lo + (draw % span) as i32;
+            }
+        }
+    }
+
+    /// Returns a uniformly distributed `f64` in `[0, 1)`.
+    fn gen_f64(&mut self) -> f64 {
+        (self.next() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
 
 #[derive(Clone, Copy)]
 struct City {
@@ -22,6 +103,9 @@ struct Particle {
     best_position: Vec<usize>,
     best_cost: f64,
     cost: f64,
+    /// The swap sequence applied last iteration, replayed as the inertia
+    /// term of the next velocity update.
+    velocity: Vec<(usize, usize)>,
 }
 
 impl Particle {
@@ -31,116 +115,572 @@ impl Particle {
             best_cost: cost,
             position,
             cost,
+            velocity: Vec::new(),
         }
     }
 }
 
-fn generate_cities() -> Vec<City> {
+fn generate_cities(rng: &mut Rng) -> Vec<City> {
     let mut cities = Vec::with_capacity(NUM_CITIES);
     for _ in 0..NUM_CITIES {
         cities.push(City {
-            x: random_range(0, 100),
-            y: random_range(0, 100),
+            x: rng.gen_range(0, 100),
+            y: rng.gen_range(0, 100),
         });
     }
     cities
 }
 
-fn random_range(min: i32, max: i32) -> i32 {
-    let epoch_time = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_nanos();
-    let seed = (epoch_time % (max - min) as u128) as i32;
-    min + seed
-}
-
-fn shuffle_vec(vec: &mut Vec<usize>) {
+fn shuffle_vec(vec: &mut Vec<usize>, rng: &mut Rng) {
     let len = vec.len();
     for i in 0..len {
-        let j = random_range(0, len as i32) as usize;
+        let j = rng.gen_range(0, len as i32) as usize;
         vec.swap(i, j);
     }
 }
 
-fn calculate_cost(route: &[usize], cities: &[City]) -> f64 {
+/// Computes the sequence of index-position transpositions that transforms
+/// `from` into `to`: for each index in turn, if the city there doesn't
+/// already match `to`, swap in the city that does. This is the "swap
+/// sequence" used as a discrete PSO particle's velocity.
+fn swap_sequence(from: &[usize], to: &[usize]) -> Vec<(usize, usize)> {
+    let n = from.len();
+    let mut working = from.to_vec();
+    let mut position_of = vec![0usize; n];
+    for (index, &city) in working.iter().enumerate() {
+        position_of[city] = index;
+    }
+
+    let mut swaps = Vec::new();
+    for i in 0..n {
+        if working[i] != to[i] {
+            let j = position_of[to[i]];
+            working.swap(i, j);
+            position_of[working[i]] = i;
+            position_of[working[j]] = j;
+            swaps.push((i, j));
+        }
+    }
+    swaps
+}
+
+fn calculate_cost(route: &[usize], dist: &[Vec<f64>]) -> f64 {
     let mut total_cost = 0.0;
     for i in 0..route.len() - 1 {
-        let city_a = &cities[route[i]];
-        let city_b = &cities[route[i + 1]];
-        total_cost += (((city_a.x - city_b.x).pow(2) + (city_a.y - city_b.y).pow(2)) as f64).sqrt();
+        total_cost += dist[route[i]][route[i + 1]];
     }
     // Return to the starting city
-    let start_city = &cities[route[0]];
-    let end_city = &cities[route[route.len() - 1]];
-    total_cost += (((start_city.x - end_city.x).pow(2) + (start_city.y - end_city.y).pow(2)) as f64).sqrt();
+    total_cost += dist[route[route.len() - 1]][route[0]];
     total_cost
 }
 
-fn initialize_particles(cities: &[City]) -> Vec<Particle> {
-    let mut particles = Vec::with_capacity(NUM_PARTICLES);
-    for _ in 0..NUM_PARTICLES {
-        let mut position: Vec<usize> = (0..NUM_CITIES).collect();
-        shuffle_vec(&mut position);
-        let cost = calculate_cost(&position, cities);
+/// Computes the pairwise Euclidean distance matrix for `cities`, so repeated
+/// cost evaluations (2-opt deltas, `calculate_cost`) don't redo the sqrt.
+fn distance_matrix(cities: &[City]) -> Vec<Vec<f64>> {
+    relay_distance_matrix(cities, cities.len())
+}
+
+/// Like `distance_matrix`, but prices any hop touching a relay waypoint
+/// (any index `>= num_cities`) at `RELAY_HOP_DISCOUNT` of its raw Euclidean
+/// distance. Passing `num_cities == points.len()` (no relays present)
+/// reduces to plain `distance_matrix`.
+///
+/// Note this discounts relay-to-city hops as well as relay-to-relay ones,
+/// broader than "cheaper relay-to-relay hops" as originally scoped: a
+/// relay-to-relay-only discount can never fire under single-waypoint
+/// cheapest insertion, since no inserted waypoint ever neighbors another
+/// relay in the tour. Flagging this as a deliberate widening of the
+/// original ask, not a drive-by reinterpretation — the tradeoff is that
+/// relay-mode "Best Cost" is no longer real Euclidean tour length, see the
+/// output note in `main`.
+fn relay_distance_matrix(points: &[City], num_cities: usize) -> Vec<Vec<f64>> {
+    let n = points.len();
+    let mut matrix = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..n {
+            let dx = (points[i].x - points[j].x) as f64;
+            let dy = (points[i].y - points[j].y) as f64;
+            let mut d = (dx * dx + dy * dy).sqrt();
+            if i >= num_cities || j >= num_cities {
+                d *= RELAY_HOP_DISCOUNT;
+            }
+            matrix[i][j] = d;
+        }
+    }
+    matrix
+}
+
+fn squared_distance(a: &City, b: &City) -> f64 {
+    let dx = (a.x - b.x) as f64;
+    let dy = (a.y - b.y) as f64;
+    dx * dx + dy * dy
+}
+
+/// Lloyd's k-means with k-means++ seeding: the first centroid is chosen
+/// uniformly at random, each subsequent one with probability proportional
+/// to its squared distance from the nearest centroid already chosen.
+/// Iterates assign-then-recompute until assignments stabilize or
+/// `KMEANS_MAX_ITERATIONS` is hit, and returns the `k` centroids.
+fn kmeans(points: &[City], k: usize, rng: &mut Rng) -> Vec<City> {
+    let n = points.len();
+    assert!(k > 0 && k <= n, "kmeans: k must be in 1..=points.len()");
+
+    let mut centroids = Vec::with_capacity(k);
+    centroids.push(points[rng.gen_range(0, n as i32) as usize]);
+
+    while centroids.len() < k {
+        let nearest_sq: Vec<f64> = points
+            .iter()
+            .map(|p| {
+                centroids
+                    .iter()
+                    .map(|c| squared_distance(p, c))
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+        let total: f64 = nearest_sq.iter().sum();
+
+        if total == 0.0 {
+            centroids.push(points[rng.gen_range(0, n as i32) as usize]);
+            continue;
+        }
+
+        let threshold = rng.gen_f64() * total;
+        let mut cumulative = 0.0;
+        let mut chosen = n - 1;
+        for (i, &d) in nearest_sq.iter().enumerate() {
+            cumulative += d;
+            if cumulative >= threshold {
+                chosen = i;
+                break;
+            }
+        }
+        centroids.push(points[chosen]);
+    }
+
+    let mut assignments = vec![usize::MAX; n];
+    for _ in 0..KMEANS_MAX_ITERATIONS {
+        let mut changed = false;
+        for (i, p) in points.iter().enumerate() {
+            let (closest, _) = centroids
+                .iter()
+                .enumerate()
+                .map(|(ci, c)| (ci, squared_distance(p, c)))
+                .fold((0, f64::INFINITY), |best, cur| if cur.1 < best.1 { cur } else { best });
+            if assignments[i] != closest {
+                assignments[i] = closest;
+                changed = true;
+            }
+        }
+
+        let mut sums = vec![(0i64, 0i64, 0u32); k];
+        for (i, p) in points.iter().enumerate() {
+            let c = &mut sums[assignments[i]];
+            c.0 += p.x as i64;
+            c.1 += p.y as i64;
+            c.2 += 1;
+        }
+        for (centroid, (sum_x, sum_y, count)) in centroids.iter_mut().zip(sums) {
+            if count > 0 {
+                centroid.x = (sum_x as f64 / count as f64).round() as i32;
+                centroid.y = (sum_y as f64 / count as f64).round() as i32;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    centroids
+}
+
+/// Greedily builds a tour by always hopping to the closest unvisited city,
+/// starting from `start`. Used to warm-start a fraction of the swarm with
+/// something better than a pure random shuffle.
+fn nearest_neighbor_tour(start: usize, dist: &[Vec<f64>]) -> Vec<usize> {
+    let n = dist.len();
+    let mut visited = vec![false; n];
+    let mut tour = Vec::with_capacity(n);
+
+    let mut current = start;
+    visited[current] = true;
+    tour.push(current);
+
+    for _ in 1..n {
+        let mut nearest = None;
+        let mut nearest_dist = f64::INFINITY;
+        for candidate in 0..n {
+            if !visited[candidate] && dist[current][candidate] < nearest_dist {
+                nearest_dist = dist[current][candidate];
+                nearest = Some(candidate);
+            }
+        }
+        let next = nearest.expect("at least one unvisited city remains");
+        visited[next] = true;
+        tour.push(next);
+        current = next;
+    }
+
+    tour
+}
+
+/// Repeatedly applies each improving 2-opt edge swap as soon as it is found
+/// (first improvement, not best improvement) until a full pass
+/// over all `i < j` pairs yields no further improvement. Returns whether any
+/// swap was applied.
+fn two_opt(route: &mut Vec<usize>, dist: &[Vec<f64>]) -> bool {
+    let n = route.len();
+    let mut improved_any = false;
+    loop {
+        let mut improved = false;
+        for i in 1..n - 1 {
+            for j in (i + 1)..n {
+                let a = route[i - 1];
+                let b = route[i];
+                let c = route[j];
+                let d = route[(j + 1) % n];
+                let delta = dist[a][c] + dist[b][d] - dist[a][b] - dist[c][d];
+                if delta < -1e-9 {
+                    route[i..=j].reverse();
+                    improved = true;
+                    improved_any = true;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+    improved_any
+}
+
+/// Finds the cheapest edge of `route` to splice `waypoint` into, and the
+/// resulting cost delta: `dist[a][waypoint] + dist[waypoint][b] - dist[a][b]`
+/// for the edge `(a, b)` that minimizes it.
+fn cheapest_insertion(route: &[usize], dist: &[Vec<f64>], waypoint: usize) -> (usize, f64) {
+    let n = route.len();
+    let mut best_edge = 0;
+    let mut best_delta = f64::INFINITY;
+    for i in 0..n {
+        let a = route[i];
+        let b = route[(i + 1) % n];
+        let delta = dist[a][waypoint] + dist[waypoint][b] - dist[a][b];
+        if delta < best_delta {
+            best_delta = delta;
+            best_edge = i;
+        }
+    }
+    (best_edge, best_delta)
+}
+
+/// Splices each of `waypoints` into `route` via cheapest insertion, but only
+/// where doing so actually shortens the tour. Under plain Euclidean distance
+/// a detour through an extra point never helps (triangle inequality), so
+/// `dist` is expected to be a `relay_distance_matrix`-style matrix that
+/// discounts relay hops — only then can routing through a waypoint beat the
+/// direct city-to-city edge it replaces.
+fn insert_waypoints(route: &mut Vec<usize>, dist: &[Vec<f64>], waypoints: &[usize]) {
+    for &waypoint in waypoints {
+        let (edge, delta) = cheapest_insertion(route, dist, waypoint);
+        if delta < 0.0 {
+            route.insert(edge + 1, waypoint);
+        }
+    }
+}
+
+/// Cost delta of reversing the segment `route[i..=j]` (cyclic 2-opt edge
+/// swap), without mutating `route`. Unlike `two_opt`'s inner loop this
+/// allows `i == 0`, which simulated annealing's random moves need.
+fn two_opt_segment_delta(route: &[usize], dist: &[Vec<f64>], i: usize, j: usize) -> f64 {
+    let n = route.len();
+    if i == 0 && j == n - 1 {
+        // Reversing the entire cycle just flips its traversal direction.
+        return 0.0;
+    }
+    let a = route[(i + n - 1) % n];
+    let b = route[i];
+    let c = route[j];
+    let d = route[(j + 1) % n];
+    dist[a][c] + dist[b][d] - dist[a][b] - dist[c][d]
+}
+
+/// Cost delta of removing the city at position `p` and reinserting it
+/// immediately after the city at position `q` (an "or-opt" move), without
+/// mutating `route`.
+fn or_opt_delta(route: &[usize], dist: &[Vec<f64>], p: usize, q: usize) -> f64 {
+    let n = route.len();
+    if q == (p + n - 1) % n {
+        // Reinserting right after its own predecessor puts the city back
+        // where it started.
+        return 0.0;
+    }
+    let prev = route[(p + n - 1) % n];
+    let city = route[p];
+    let next = route[(p + 1) % n];
+    let removal_gain = dist[prev][city] + dist[city][next] - dist[prev][next];
+
+    let a = route[q];
+    let b = route[(q + 1) % n];
+    let insertion_cost = dist[a][city] + dist[city][b] - dist[a][b];
+
+    insertion_cost - removal_gain
+}
+
+/// Applies the or-opt move described in `or_opt_delta`: removes the city at
+/// position `p` and reinserts it right after the city that was at `q`.
+fn apply_or_opt_move(route: &mut Vec<usize>, p: usize, q: usize) {
+    let city = route.remove(p);
+    let a_new_index = if q < p { q } else { q - 1 };
+    route.insert(a_new_index + 1, city);
+}
+
+/// Time-limited simulated annealing. Runs until `time_limit` elapses,
+/// proposing either a random 2-opt segment reversal or an or-opt relocation
+/// at each step, accepting worsening moves with probability `exp(-delta /
+/// temp)` under a geometric cooling schedule. Returns the best tour seen.
+fn simulated_annealing(
+    route: &[usize],
+    dist: &[Vec<f64>],
+    time_limit: Duration,
+    rng: &mut Rng,
+) -> (Vec<usize>, f64) {
+    let n = route.len();
+    if n < 2 {
+        // No pair of cities to move between, so there's nothing to anneal.
+        return (route.to_vec(), calculate_cost(route, dist));
+    }
+
+    let mut route = route.to_vec();
+    let mut cost = calculate_cost(&route, dist);
+    let mut best_route = route.clone();
+    let mut best_cost = cost;
+
+    let start = Instant::now();
+    let time_limit_secs = time_limit.as_secs_f64();
+    let mut temp = SA_START_TEMP;
+    let mut iterations: u64 = 0;
+
+    loop {
+        if iterations % SA_CLOCK_CHECK_INTERVAL == 0 {
+            let elapsed_secs = start.elapsed().as_secs_f64();
+            if elapsed_secs >= time_limit_secs {
+                break;
+            }
+            let frac = (elapsed_secs / time_limit_secs).min(1.0);
+            temp = SA_START_TEMP * (SA_END_TEMP / SA_START_TEMP).powf(frac);
+        }
+        iterations += 1;
+
+        let i = rng.gen_range(0, n as i32) as usize;
+        let mut j = rng.gen_range(0, n as i32) as usize;
+        while j == i {
+            j = rng.gen_range(0, n as i32) as usize;
+        }
+        let use_or_opt = rng.gen_f64() < 0.5;
+
+        let delta = if use_or_opt {
+            or_opt_delta(&route, dist, i, j)
+        } else {
+            let (lo, hi) = if i < j { (i, j) } else { (j, i) };
+            two_opt_segment_delta(&route, dist, lo, hi)
+        };
+
+        let accept = delta < 0.0 || rng.gen_f64() < (-delta / temp).exp();
+        if accept {
+            if use_or_opt {
+                apply_or_opt_move(&mut route, i, j);
+            } else {
+                let (lo, hi) = if i < j { (i, j) } else { (j, i) };
+                route[lo..=hi].reverse();
+            }
+            cost += delta;
+
+            if cost < best_cost {
+                best_cost = cost;
+                best_route = route.clone();
+            }
+        }
+    }
+
+    (best_route, best_cost)
+}
+
+/// Exact Held-Karp solver via bitmask DP: `dp[mask][i]` is the minimum cost
+/// of a path starting at city 0, visiting exactly the cities in `mask`
+/// (which always includes 0 and `i`), and ending at `i`. `O(2^n * n^2)` time
+/// and `O(2^n * n)` memory, so it's only viable for small instances.
+fn held_karp(dist: &[Vec<f64>]) -> (Vec<usize>, f64) {
+    let n = dist.len();
+    assert!(
+        n <= HELD_KARP_MAX_CITIES,
+        "held_karp: {} cities exceeds the practical limit of {}",
+        n,
+        HELD_KARP_MAX_CITIES
+    );
+
+    if n <= 1 {
+        return ((0..n).collect(), 0.0);
+    }
+
+    let num_masks = 1usize << n;
+    let mut dp = vec![vec![f64::INFINITY; n]; num_masks];
+    let mut parent = vec![vec![usize::MAX; n]; num_masks];
+    dp[1][0] = 0.0;
+
+    for mask in 1..num_masks {
+        if mask & 1 == 0 {
+            continue;
+        }
+        for i in 0..n {
+            if mask & (1 << i) == 0 || dp[mask][i].is_infinite() {
+                continue;
+            }
+            for j in 0..n {
+                if mask & (1 << j) != 0 {
+                    continue;
+                }
+                let next_mask = mask | (1 << j);
+                let candidate_cost = dp[mask][i] + dist[i][j];
+                if candidate_cost < dp[next_mask][j] {
+                    dp[next_mask][j] = candidate_cost;
+                    parent[next_mask][j] = i;
+                }
+            }
+        }
+    }
+
+    let full_mask = num_masks - 1;
+    let mut best_cost = f64::INFINITY;
+    let mut best_last = 0;
+    for i in 1..n {
+        let cost = dp[full_mask][i] + dist[i][0];
+        if cost < best_cost {
+            best_cost = cost;
+            best_last = i;
+        }
+    }
+
+    let mut route = Vec::with_capacity(n);
+    let mut mask = full_mask;
+    let mut city = best_last;
+    while city != 0 || mask != 1 {
+        route.push(city);
+        let prev_city = parent[mask][city];
+        mask &= !(1 << city);
+        city = prev_city;
+    }
+    route.push(0);
+    route.reverse();
+
+    (route, best_cost)
+}
+
+fn initialize_particles(dist: &[Vec<f64>], particle_count: usize, rng: &mut Rng) -> Vec<Particle> {
+    let n = dist.len();
+    let mut particles = Vec::with_capacity(particle_count);
+    let nearest_neighbor_seed_count =
+        (particle_count as f64 * NEAREST_NEIGHBOR_SEED_FRACTION) as usize;
+    for i in 0..particle_count {
+        let position = if i < nearest_neighbor_seed_count {
+            let start = rng.gen_range(0, n as i32) as usize;
+            nearest_neighbor_tour(start, dist)
+        } else {
+            let mut position: Vec<usize> = (0..n).collect();
+            shuffle_vec(&mut position, rng);
+            position
+        };
+        let cost = calculate_cost(&position, dist);
         particles.push(Particle::new(position, cost));
     }
     particles
 }
 
-fn apply_mutation_and_gaussian(position: &mut Vec<usize>) {
+fn apply_mutation_and_gaussian(position: &mut Vec<usize>, rng: &mut Rng) {
+    let n = position.len();
+
     // Mutation
-    if random_range(0, 100) as f64 / 100.0 < MUTATION_RATE {
-        let index1 = random_range(0, NUM_CITIES as i32) as usize;
-        let index2 = random_range(0, NUM_CITIES as i32) as usize;
+    if rng.gen_f64() < MUTATION_RATE {
+        let index1 = rng.gen_range(0, n as i32) as usize;
+        let index2 = rng.gen_range(0, n as i32) as usize;
         position.swap(index1, index2);
     }
 
     // Simple Gaussian-like perturbation using random swap
-    if random_range(0, 100) as f64 / 100.0 < MUTATION_RATE {
-        let index1 = random_range(0, NUM_CITIES as i32) as usize;
-        let index2 = random_range(0, NUM_CITIES as i32) as usize;
+    if rng.gen_f64() < MUTATION_RATE {
+        let index1 = rng.gen_range(0, n as i32) as usize;
+        let index2 = rng.gen_range(0, n as i32) as usize;
         position.swap(index1, index2);
     }
 }
 
-fn prune_particles(swarm: &mut Vec<Particle>, cities: &[City]) {
+fn prune_particles(swarm: &mut Vec<Particle>, dist: &[Vec<f64>], rng: &mut Rng) {
     // Sort swarm by cost in ascending order
     swarm.sort_by(|a, b| a.cost.partial_cmp(&b.cost).unwrap());
 
     // Remove the worst 10% of particles
-    let prune_count = NUM_PARTICLES * PRUNE_PERCENTAGE / 100;
+    let n = dist.len();
+    let particle_count = swarm.len();
+    let prune_count = particle_count * PRUNE_PERCENTAGE / 100;
     for i in 0..prune_count {
-        let particle = &mut swarm[NUM_PARTICLES - 1 - i];
-        particle.position = (0..NUM_CITIES).collect();
-        shuffle_vec(&mut particle.position);
-        particle.cost = calculate_cost(&particle.position, cities);
+        let particle = &mut swarm[particle_count - 1 - i];
+        particle.position = (0..n).collect();
+        shuffle_vec(&mut particle.position, rng);
+        particle.cost = calculate_cost(&particle.position, dist);
         particle.best_position = particle.position.clone();
         particle.best_cost = particle.cost;
+        particle.velocity.clear();
     }
 }
 
+/// Advances each particle one discrete-PSO step. A particle's velocity is a
+/// swap sequence: the inertia term replays last iteration's swaps, then the
+/// cognitive and social terms apply the swap sequences toward the
+/// particle's personal best and the swarm's global best, each swap kept
+/// with probability equal to its (randomized, clamped to [0,1]) component
+/// weight. The swaps actually applied become next iteration's velocity.
 fn update_particles(
     swarm: &mut Vec<Particle>,
     global_best_position: &mut Vec<usize>,
     global_best_cost: &mut f64,
-    cities: &[City],
+    dist: &[Vec<f64>],
+    inertia_weight: f64,
+    rng: &mut Rng,
 ) {
     for particle in swarm.iter_mut() {
-        // Shuffle starting positions to encourage exploration
-        shuffle_vec(&mut particle.position);
+        let mut new_velocity = Vec::new();
+
+        // Inertia: replay last iteration's swap sequence.
+        for &(i, j) in &particle.velocity {
+            if rng.gen_f64() < inertia_weight {
+                particle.position.swap(i, j);
+                new_velocity.push((i, j));
+            }
+        }
 
-        // Update based on personal best and global best
-        for i in 0..NUM_CITIES {
-            if random_range(0, 100) as f64 / 100.0 < COGNITIVE_COMPONENT {
-                particle.position.swap(i, particle.best_position[i]);
+        // Cognitive component: move toward the particle's personal best.
+        let cognitive_prob = (COGNITIVE_COMPONENT * rng.gen_f64()).min(1.0);
+        for (i, j) in swap_sequence(&particle.position, &particle.best_position) {
+            if rng.gen_f64() < cognitive_prob {
+                particle.position.swap(i, j);
+                new_velocity.push((i, j));
             }
-            if random_range(0, 100) as f64 / 100.0 < SOCIAL_COMPONENT {
-                particle.position.swap(i, global_best_position[i]);
+        }
+
+        // Social component: move toward the swarm's global best.
+        let social_prob = (SOCIAL_COMPONENT * rng.gen_f64()).min(1.0);
+        for (i, j) in swap_sequence(&particle.position, global_best_position) {
+            if rng.gen_f64() < social_prob {
+                particle.position.swap(i, j);
+                new_velocity.push((i, j));
             }
         }
 
         // Apply mutation and simple Gaussian-like perturbation
-        apply_mutation_and_gaussian(&mut particle.position);
+        apply_mutation_and_gaussian(&mut particle.position, rng);
 
-        particle.cost = calculate_cost(&particle.position, cities);
+        particle.velocity = new_velocity;
+        particle.cost = calculate_cost(&particle.position, dist);
 
         // Aggressively reward the particle if it finds a better solution
         if particle.cost < particle.best_cost {
@@ -156,26 +696,372 @@ fn update_particles(
     }
 
     // Prune the worst-performing particles
-    prune_particles(swarm, cities);
+    prune_particles(swarm, dist, rng);
 }
 
-fn main() {
-    let cities = generate_cities();
-    let mut swarm = initialize_particles(&cities);
+/// Runs the PSO loop (with periodic and final 2-opt polishing) to completion
+/// and returns the best tour found.
+fn run_pso(
+    dist: &[Vec<f64>],
+    particle_count: usize,
+    iterations: usize,
+    rng: &mut Rng,
+) -> (Vec<usize>, f64) {
+    let mut swarm = initialize_particles(dist, particle_count, rng);
 
     let mut global_best_position = swarm[0].best_position.clone();
     let mut global_best_cost = swarm[0].best_cost;
 
-    for _ in 0..MAX_ITERATIONS {
+    for iteration in 0..iterations {
+        // Linearly anneal the inertia weight from its initial to final value
+        // over the course of the run.
+        let inertia_weight = INITIAL_INERTIA_WEIGHT
+            - (INITIAL_INERTIA_WEIGHT - FINAL_INERTIA_WEIGHT)
+                * (iteration as f64 / iterations.max(1) as f64);
+
         update_particles(
             &mut swarm,
             &mut global_best_position,
             &mut global_best_cost,
-            &cities,
+            dist,
+            inertia_weight,
+            rng,
         );
+
+        // Memetic step: periodically polish each particle's personal best
+        // with 2-opt, which PSO's swap-based moves rarely discover on their own.
+        if (iteration + 1) % TWO_OPT_INTERVAL == 0 {
+            for particle in swarm.iter_mut() {
+                if two_opt(&mut particle.best_position, dist) {
+                    particle.best_cost = calculate_cost(&particle.best_position, dist);
+                    if particle.best_cost < global_best_cost {
+                        global_best_cost = particle.best_cost;
+                        global_best_position = particle.best_position.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    // Final refinement: 2-opt the overall best tour to clean up any
+    // crossing edges PSO left behind.
+    if two_opt(&mut global_best_position, dist) {
+        global_best_cost = calculate_cost(&global_best_position, dist);
+    }
+
+    (global_best_position, global_best_cost)
+}
+
+/// Loads cities from a CSV file of `x,y` rows. Non-numeric rows (e.g. an
+/// `x,y` header) are skipped.
+fn load_cities_from_csv(path: &str) -> Vec<City> {
+    let content =
+        std::fs::read_to_string(path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e));
+    let mut cities = Vec::new();
+    for line in content.lines() {
+        let mut fields = line.split(',');
+        let x = fields.next().and_then(|s| s.trim().parse::<i32>().ok());
+        let y = fields.next().and_then(|s| s.trim().parse::<i32>().ok());
+        if let (Some(x), Some(y)) = (x, y) {
+            cities.push(City { x, y });
+        }
+    }
+    cities
+}
+
+/// Parsed command-line configuration.
+struct Config {
+    input_path: Option<String>,
+    strategy: Strategy,
+    seed: u64,
+    particles: usize,
+    iterations: usize,
+    time_limit: Duration,
+    relays: usize,
+}
+
+fn print_usage_and_exit() -> ! {
+    eprintln!(
+        "usage: tsp [INPUT.csv] [--strategy pso|sa|2opt|exact] [--seed N] \
+         [--particles N] [--iterations N] [--time-limit SECONDS] [--relays N]"
+    );
+    std::process::exit(1);
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
+
+/// Returns the message for the first degenerate input/config combination
+/// found that would otherwise panic deep inside a solver, or `None` if
+/// `config` is safe to dispatch. Kept separate from `validate_config` so the
+/// checks themselves are plain, testable logic with no process exit.
+fn config_validation_error(config: &Config, cities: &[City]) -> Option<String> {
+    if cities.is_empty() {
+        return Some("input must contain at least one city".to_string());
+    }
+    if config.strategy == Strategy::Pso && config.particles == 0 {
+        return Some("--particles must be greater than 0 for the pso strategy".to_string());
+    }
+    if config.strategy == Strategy::Exact && cities.len() > HELD_KARP_MAX_CITIES {
+        return Some(format!(
+            "--strategy exact only supports up to {} cities (got {})",
+            HELD_KARP_MAX_CITIES,
+            cities.len()
+        ));
+    }
+    if config.relays > cities.len() {
+        return Some(format!(
+            "--relays must not exceed the number of cities ({}, got {})",
+            cities.len(),
+            config.relays
+        ));
+    }
+    None
+}
+
+/// Rejects input/config combinations that would otherwise panic deep inside
+/// a solver, with a clean CLI error instead.
+fn validate_config(config: &Config, cities: &[City]) {
+    if let Some(message) = config_validation_error(config, cities) {
+        exit_with_error(&message);
+    }
+}
+
+/// A `--time-limit` of `secs` seconds is only safe to hand to
+/// `Duration::from_secs_f64`, which panics on negative or non-finite input.
+fn time_limit_is_valid(secs: f64) -> bool {
+    secs.is_finite() && secs >= 0.0
+}
+
+fn parse_args() -> Config {
+    let mut config = Config {
+        input_path: None,
+        strategy: Strategy::Pso,
+        seed: 42,
+        particles: NUM_PARTICLES,
+        iterations: MAX_ITERATIONS,
+        time_limit: Duration::from_secs(5),
+        relays: 0,
+    };
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--strategy" => {
+                i += 1;
+                config.strategy = match args.get(i).map(String::as_str) {
+                    Some("pso") => Strategy::Pso,
+                    Some("sa") => Strategy::SimulatedAnnealing,
+                    Some("2opt") => Strategy::TwoOptOnly,
+                    Some("exact") => Strategy::Exact,
+                    _ => print_usage_and_exit(),
+                };
+            }
+            "--seed" => {
+                i += 1;
+                config.seed = args
+                    .get(i)
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or_else(|| print_usage_and_exit());
+            }
+            "--particles" => {
+                i += 1;
+                config.particles = args
+                    .get(i)
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or_else(|| print_usage_and_exit());
+            }
+            "--iterations" => {
+                i += 1;
+                config.iterations = args
+                    .get(i)
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or_else(|| print_usage_and_exit());
+            }
+            "--time-limit" => {
+                i += 1;
+                let secs: f64 = args
+                    .get(i)
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or_else(|| print_usage_and_exit());
+                if !time_limit_is_valid(secs) {
+                    exit_with_error("--time-limit must be a finite, non-negative number of seconds");
+                }
+                config.time_limit = Duration::from_secs_f64(secs);
+            }
+            "--relays" => {
+                i += 1;
+                config.relays = args
+                    .get(i)
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or_else(|| print_usage_and_exit());
+            }
+            arg if !arg.starts_with("--") && config.input_path.is_none() => {
+                config.input_path = Some(arg.to_string());
+            }
+            _ => print_usage_and_exit(),
+        }
+        i += 1;
+    }
+
+    config
+}
+
+fn main() {
+    let config = parse_args();
+    let mut rng = Rng::new(config.seed);
+
+    let cities = match &config.input_path {
+        Some(path) => load_cities_from_csv(path),
+        None => generate_cities(&mut rng),
+    };
+    validate_config(&config, &cities);
+    let dist = distance_matrix(&cities);
+
+    let (mut route, mut cost) = match config.strategy {
+        Strategy::Pso => run_pso(&dist, config.particles, config.iterations, &mut rng),
+        Strategy::SimulatedAnnealing => {
+            let start_route: Vec<usize> = (0..cities.len()).collect();
+            simulated_annealing(&start_route, &dist, config.time_limit, &mut rng)
+        }
+        Strategy::TwoOptOnly => {
+            let mut route = nearest_neighbor_tour(0, &dist);
+            two_opt(&mut route, &dist);
+            let cost = calculate_cost(&route, &dist);
+            (route, cost)
+        }
+        Strategy::Exact => held_karp(&dist),
+    };
+
+    // Steiner-style relay mode: offer k-means cluster centroids as optional
+    // waypoints the tour may splice itself through. Hops touching a relay
+    // are priced at a discount (see `RELAY_HOP_DISCOUNT`), so a detour
+    // through one can actually undercut the direct city-to-city edge it
+    // replaces instead of merely adding triangle-inequality slack.
+    if config.relays > 0 {
+        let relay_points = kmeans(&cities, config.relays, &mut rng);
+        let num_cities = cities.len();
+        let mut waypoints = cities.clone();
+        waypoints.extend_from_slice(&relay_points);
+        let relay_dist = relay_distance_matrix(&waypoints, num_cities);
+
+        let relay_indices: Vec<usize> = (num_cities..waypoints.len()).collect();
+        insert_waypoints(&mut route, &relay_dist, &relay_indices);
+        cost = calculate_cost(&route, &relay_dist);
     }
 
     // Output the best route found
-    println!("Best Route: {:?}", global_best_position);
-    println!("Best Cost: {}", global_best_cost);
+    println!("Best Route: {:?}", route);
+    println!("Best Cost: {}", cost);
+    if config.relays > 0 {
+        println!(
+            "note: cost above is computed under the relay discount model \
+             (relay-touching hops priced at {}x), not real Euclidean tour length",
+            RELAY_HOP_DISCOUNT
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// First 5 outputs of seeded Rng::new(42), cross-checked against an
+    /// independent splitmix64-seed / xoshiro256++-step implementation, to
+    /// catch regressions in either the seeding or the stepping.
+    #[test]
+    fn rng_matches_known_xoshiro256pp_sequence() {
+        let mut rng = Rng::new(42);
+        let expected: [u64; 5] = [
+            0xd0764d4f4476689f,
+            0x519e4174576f3791,
+            0xfbe07cfb0c24ed8c,
+            0xb37d9f600cd835b8,
+            0xcb231c3874846a73,
+        ];
+        for want in expected {
+            assert_eq!(rng.next(), want);
+        }
+    }
+
+    #[test]
+    fn time_limit_validation_rejects_non_finite_and_negative() {
+        assert!(time_limit_is_valid(5.0));
+        assert!(time_limit_is_valid(0.0));
+        assert!(!time_limit_is_valid(-1.0));
+        assert!(!time_limit_is_valid(f64::NAN));
+        assert!(!time_limit_is_valid(f64::INFINITY));
+    }
+
+    #[test]
+    fn config_validation_rejects_degenerate_inputs() {
+        fn cfg(strategy: Strategy, particles: usize, relays: usize) -> Config {
+            Config {
+                input_path: None,
+                strategy,
+                seed: 1,
+                particles,
+                iterations: 10,
+                time_limit: Duration::from_secs(1),
+                relays,
+            }
+        }
+
+        let one_city = vec![City { x: 0, y: 0 }];
+        let too_many_cities: Vec<City> = (0..(HELD_KARP_MAX_CITIES as i32 + 1))
+            .map(|i| City { x: i, y: 0 })
+            .collect();
+
+        assert!(config_validation_error(&cfg(Strategy::Pso, 10, 0), &[]).is_some());
+        assert!(config_validation_error(&cfg(Strategy::Pso, 0, 0), &one_city).is_some());
+        assert!(config_validation_error(&cfg(Strategy::Exact, 10, 0), &too_many_cities).is_some());
+        assert!(config_validation_error(&cfg(Strategy::Pso, 10, 2), &one_city).is_some());
+        assert!(config_validation_error(&cfg(Strategy::Pso, 10, 0), &one_city).is_none());
+    }
+
+    /// Visits every permutation of `arr[k..]` in place, calling `f` on the
+    /// full slice each time (swap, recurse, swap back).
+    fn permute<F: FnMut(&[usize])>(arr: &mut Vec<usize>, k: usize, f: &mut F) {
+        if k == arr.len() {
+            f(arr);
+            return;
+        }
+        for i in k..arr.len() {
+            arr.swap(k, i);
+            permute(arr, k + 1, f);
+            arr.swap(k, i);
+        }
+    }
+
+    #[test]
+    fn held_karp_matches_brute_force_on_small_instance() {
+        let cities = vec![
+            City { x: 0, y: 0 },
+            City { x: 1, y: 5 },
+            City { x: 5, y: 2 },
+            City { x: 3, y: 3 },
+            City { x: 6, y: 6 },
+        ];
+        let dist = distance_matrix(&cities);
+        let (_, exact_cost) = held_karp(&dist);
+
+        let mut rest: Vec<usize> = (1..cities.len()).collect();
+        let mut best = f64::INFINITY;
+        {
+            let mut visit = |perm: &[usize]| {
+                let mut route = vec![0];
+                route.extend_from_slice(perm);
+                let cost = calculate_cost(&route, &dist);
+                if cost < best {
+                    best = cost;
+                }
+            };
+            permute(&mut rest, 0, &mut visit);
+        }
+
+        assert!((exact_cost - best).abs() < 1e-9);
+    }
 }